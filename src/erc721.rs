@@ -16,7 +16,8 @@ pub trait Erc721Params {
 
 sol_storage! {
     pub struct Erc721<T> {
-        /// Token id to owner map
+        /// Token id to owner map. Only explicitly written for individually minted tokens and
+        /// for the first token of each `_mint_consecutive` batch; see [`Erc721::owner_of`].
         mapping(uint256 => address) _owners;
         /// User to balance map
         mapping(address => uint256) _balances;
@@ -24,6 +25,22 @@ sol_storage! {
         mapping(uint256 => address) _approvals;
         /// User to operator map (the operator can manage all NFTs of the owner.)
         mapping(address => mapping(address => bool)) _approvals_for_all;
+        /// High-water mark of every token id ever minted (individually or via
+        /// `_mint_consecutive`); bounds `owner_of`'s downward scan.
+        uint256 _current_index;
+        /// Marks a token id as burned, so `owner_of` stops resolving it (or scanning past it on
+        /// behalf of a later id in the same batch) to an owner.
+        mapping(uint256 => bool) _burned;
+        /// Number of currently-minted tokens; also the length of the `_all_tokens` array.
+        uint256 _all_tokens_length;
+        /// Token id to its index in the conceptual `_all_tokens` array.
+        mapping(uint256 => uint256) _all_tokens_index;
+        /// Conceptual array of all minted token ids, indexed `0..total_supply()`.
+        mapping(uint256 => uint256) _all_tokens;
+        /// Token id to its index in its owner's conceptual `_owned_tokens` array.
+        mapping(uint256 => uint256) _owned_tokens_index;
+        /// Owner to conceptual array of token ids it holds, indexed `0..balance_of(owner)`.
+        mapping(address => mapping(uint256 => uint256)) _owned_tokens;
         /// Used to allow [`Erc721Params`]
         PhantomData<T> phantom;
     }
@@ -42,6 +59,7 @@ sol! {
     error NotMinted(uint256 token_id);
     error UnsafeRecipient(address recipient);
     error CallFailed();
+    error InvalidQuantity(uint256 quantity);
 }
 
 sol_interface! {
@@ -58,6 +76,7 @@ pub enum Erc721Error {
     NotMinted(NotMinted),
     UnsafeRecipient(UnsafeRecipient),
     CallFailed(CallFailed),
+    InvalidQuantity(InvalidQuantity),
 }
 
 impl From<Erc721Error> for Vec<u8> {
@@ -70,6 +89,7 @@ impl From<Erc721Error> for Vec<u8> {
             Erc721Error::NotMinted(e) => e.encode(),
             Erc721Error::UnsafeRecipient(e) => e.encode(),
             Erc721Error::CallFailed(e) => e.encode(),
+            Erc721Error::InvalidQuantity(e) => e.encode(),
         }
     }
 }
@@ -77,6 +97,17 @@ impl From<Erc721Error> for Vec<u8> {
 const ADDRESS_ZERO: Address = Address(FixedBytes([0u8; 20]));
 const ERC721_TOKEN_RECEIVER_ID: u32 = 0x150b7a02;
 
+/// `bytes4(keccak256("supportsInterface(bytes4)"))`, the ERC-165 interface id itself.
+const IERC165_ID: u32 = 0x01ffc9a7;
+/// The ERC-721 core interface id (XOR of the selectors of the base ERC-721 methods).
+const IERC721_ID: u32 = 0x80ac58cd;
+/// The ERC-721 Metadata extension interface id (`name`, `symbol`, `tokenURI`).
+const IERC721_METADATA_ID: u32 = 0x5b5e139f;
+/// The ERC-2981 royalty extension interface id (`royaltyInfo`).
+const IERC2981_ID: u32 = 0x2a55205a;
+/// The ERC-721 Enumerable extension interface id (`totalSupply`, `tokenByIndex`, `tokenOfOwnerByIndex`).
+const IERC721_ENUMERABLE_ID: u32 = 0x780e9d63;
+
 // These methods are external to other contracts
 #[external]
 impl<T: Erc721Params> Erc721<T> {
@@ -92,8 +123,65 @@ impl<T: Erc721Params> Erc721<T> {
         Ok(self._balances.get(owner))
     }
 
+    /// Resolves the owner of `token_id`. For tokens minted via `_mint_consecutive`, only the
+    /// first id of each batch has its owner slot written, so an unset slot is resolved by
+    /// scanning downward for the nearest explicitly-set, non-burned owner (the batch start).
+    ///
+    /// `_current_index` is a high-water mark bumped by both `_mint` and `_mint_consecutive` to
+    /// cover every token id ever handed out, so ids at or above it can never have been minted —
+    /// rejecting those up front keeps this a bounded lookup instead of an O(token_id) scan down
+    /// to zero for arbitrary/never-minted ids.
     pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
-        Ok(self._owners.get(token_id))
+        if token_id >= self._current_index.get() {
+            return Err(Erc721Error::NotMinted(NotMinted { token_id }));
+        }
+
+        if self._burned.get(token_id) {
+            return Err(Erc721Error::NotMinted(NotMinted { token_id }));
+        }
+
+        let mut i = token_id;
+        loop {
+            if !self._burned.get(i) {
+                let owner = self._owners.get(i);
+                if owner != ADDRESS_ZERO {
+                    return Ok(owner);
+                }
+            }
+
+            if i.is_zero() {
+                break;
+            }
+            i -= U256::from(1);
+        }
+
+        Err(Erc721Error::NotMinted(NotMinted { token_id }))
+    }
+
+    /// Total number of tokens currently minted and not yet burned, across both `_mint` and
+    /// `_mint_consecutive`.
+    pub fn total_supply(&self) -> Result<U256, Erc721Error> {
+        Ok(self._all_tokens_length.get())
+    }
+
+    /// Returns the id of the token at `index` in the full enumeration of minted tokens.
+    pub fn token_by_index(&self, index: U256) -> Result<U256, Erc721Error> {
+        if index >= self._all_tokens_length.get() {
+            return Err(Erc721Error::NotMinted(NotMinted { token_id: index }));
+        }
+        Ok(self._all_tokens.get(index))
+    }
+
+    /// Returns the id of the token at `index` in `owner`'s enumeration of held tokens.
+    pub fn token_of_owner_by_index(
+        &self,
+        owner: Address,
+        index: U256,
+    ) -> Result<U256, Erc721Error> {
+        if index >= self._balances.get(owner) {
+            return Err(Erc721Error::NotMinted(NotMinted { token_id: index }));
+        }
+        Ok(self._owned_tokens.get(owner).get(index))
     }
 
     pub fn get_approved(&self, token_id: U256) -> Result<Address, Erc721Error> {
@@ -110,7 +198,7 @@ impl<T: Erc721Params> Erc721<T> {
 
     pub fn approve(&mut self, spender: Address, token_id: U256) -> Result<(), Erc721Error> {
         // address owner = _ownerOf[id];
-        let owner = self._owners.getter(token_id).get();
+        let owner = self.owner_of(token_id)?;
 
         // require(msg.sender == owner || isApprovedForAll[owner][msg.sender], "NOT_AUTHORIZED");
         if msg::sender() != owner && !self._approvals_for_all.get(owner).get(msg::sender()) {
@@ -161,8 +249,8 @@ impl<T: Erc721Params> Erc721<T> {
         token_id: U256,
     ) -> Result<(), Erc721Error> {
         // require(from == _ownerOf[id], "WRONG_FROM");
-        let mut owner_of_id = self._owners.setter(token_id);
-        if owner_of_id.get() != from {
+        let current_owner = self.owner_of(token_id)?;
+        if current_owner != from {
             return Err(Erc721Error::NotOwner(NotOwner {
                 account: from,
                 token_id,
@@ -176,7 +264,7 @@ impl<T: Erc721Params> Erc721<T> {
 
         // require(msg.sender == from || isApprovedForAll[from][msg.sender] || msg.sender == getApproved[id], "NOT_AUTHORIZED");
         if msg::sender() != from
-            && self._approvals_for_all.get(from).get(msg::sender())
+            && !self._approvals_for_all.get(from).get(msg::sender())
             && msg::sender() != self._approvals.get(token_id)
         {
             return Err(Erc721Error::NotAuthorized(NotAuthorized {
@@ -184,6 +272,17 @@ impl<T: Erc721Params> Erc721<T> {
             }));
         }
 
+        // A self-transfer doesn't change ownership, so skip the enumeration shuffle entirely:
+        // `_remove_token_from_owner_enumeration` computes its swap index from `from`'s balance
+        // *before* it's decremented, and re-adding at `to`'s (same, still pre-decrement) balance
+        // would land the token outside the `[0, balance)` window `token_of_owner_by_index` checks.
+        if from != to {
+            // Enumeration indices are swap-and-popped against each owner's *current* balance, so
+            // this must run before the balance updates below.
+            self._remove_token_from_owner_enumeration(from, token_id);
+            self._add_token_to_owner_enumeration(to, token_id, self._balances.get(to));
+        }
+
         // _balanceOf[from]--;
         let mut from_balance = self._balances.setter(from);
         let new_from_balance = from_balance.get() - U256::from(1);
@@ -194,8 +293,20 @@ impl<T: Erc721Params> Erc721<T> {
         let new_to_balance = to_balance.get() + U256::from(1);
         to_balance.set(new_to_balance);
 
+        // If the next id is still lazily owned through this slot, anchor it to `from` first, so
+        // overwriting this slot below doesn't hand every later token in the batch to `to`.
+        let next_id = token_id + U256::from(1);
+        if next_id < self._current_index.get()
+            && self._owners.get(next_id) == ADDRESS_ZERO
+            && !self._burned.get(next_id)
+        {
+            self._owners.setter(next_id).set(from);
+        }
+
         // _ownerOf[id] = to;
-        owner_of_id.set(to);
+        // Materializes the slot even for lazily-owned (batch-minted) tokens, so future lookups
+        // and transfers of this id are a direct hit instead of a scan.
+        self._owners.setter(token_id).set(to);
 
         // delete getApproved[id];
         self._approvals.setter(token_id).set(ADDRESS_ZERO);
@@ -214,10 +325,38 @@ impl<T: Erc721Params> Erc721<T> {
         // transferFrom(from, to, id);
         self.transfer_from(from, to, token_id)?;
 
-        self._check_recipient_is_valid(from, to, token_id)?;
+        self._check_recipient_is_valid(from, to, token_id, &[])?;
 
         Ok(())
     }
+
+    /// Same as [`Self::safe_transfer_from`], but forwards `data` into the recipient's
+    /// `onERC721Received` hook, for integrations (bridges, escrow receivers) that encode
+    /// routing or metadata bytes alongside the transfer.
+    pub fn safe_transfer_from_with_data(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        // transferFrom(from, to, id);
+        self.transfer_from(from, to, token_id)?;
+
+        self._check_recipient_is_valid(from, to, token_id, &data)?;
+
+        Ok(())
+    }
+
+    /// ERC-165 introspection, letting callers discover which interfaces this contract implements.
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> Result<bool, Erc721Error> {
+        let id = u32::from_be_bytes(interface_id.0);
+        Ok(id == IERC165_ID
+            || id == IERC721_ID
+            || id == IERC721_METADATA_ID
+            || id == IERC2981_ID
+            || id == IERC721_ENUMERABLE_ID)
+    }
 }
 
 // internal mint+burn methods
@@ -234,6 +373,9 @@ impl<T: Erc721Params> Erc721<T> {
             return Err(Erc721Error::AlreadyMinted(AlreadyMinted { token_id }));
         }
 
+        self._add_token_to_owner_enumeration(to, token_id, self._balances.get(to));
+        self._add_token_to_all_tokens_enumeration(token_id);
+
         // _balanceOf[to]++;
         let mut to_balance = self._balances.setter(to);
         let new_to_balance = to_balance.get() + U256::from(1);
@@ -242,6 +384,16 @@ impl<T: Erc721Params> Erc721<T> {
         // _ownerOf[id] = to;
         owner_of_id.set(to);
 
+        // Runs after the last use of `owner_of_id`, which holds a mutable borrow of `_owners`.
+        self._add_token_to_owner_enumeration(to, token_id, owner_index);
+        self._add_token_to_all_tokens_enumeration(token_id);
+
+        // Bump the high-water mark so `owner_of`'s bounds check covers this id too, even though
+        // it wasn't handed out by `_mint_consecutive`.
+        if token_id >= self._current_index.get() {
+            self._current_index.set(token_id + U256::from(1));
+        }
+
         // emit Transfer(address(0), to, id);
         evm::log(Transfer {
             from: ADDRESS_ZERO,
@@ -253,28 +405,41 @@ impl<T: Erc721Params> Erc721<T> {
     }
 
     pub fn _burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
-        // address owner = _ownerOf[id];
-        let mut owner = self._owners.setter(token_id);
+        // address owner = _ownerOf[id]; (resolved via scan, to support lazily-owned batch tokens)
+        let owner = self.owner_of(token_id)?;
 
-        // require(owner != address(0), "NOT_MINTED");
-        if owner.get() == ADDRESS_ZERO {
-            return Err(Erc721Error::NotMinted(NotMinted { token_id }));
-        }
+        self._remove_token_from_owner_enumeration(owner, token_id);
+        self._remove_token_from_all_tokens_enumeration(token_id);
 
         // _balanceOf[owner]--;
-        let mut owner_balance = self._balances.setter(owner.get());
+        let mut owner_balance = self._balances.setter(owner);
         let new_owner_balance = owner_balance.get() - U256::from(1);
         owner_balance.set(new_owner_balance);
 
-        // delete _ownerOf[id];
-        owner.set(ADDRESS_ZERO);
+        // If the next id is still lazily owned through this slot (no owner of its own, not
+        // itself burned), carry the anchor forward onto it first — otherwise marking `token_id`
+        // burned below would hide the only owner data that id's `owner_of` scan depends on,
+        // orphaning every later token in the batch. Standard ERC721A burn technique.
+        let next_id = token_id + U256::from(1);
+        if next_id < self._current_index.get()
+            && self._owners.get(next_id) == ADDRESS_ZERO
+            && !self._burned.get(next_id)
+        {
+            self._owners.setter(next_id).set(owner);
+        }
+
+        // Materialize the owner slot before marking it burned. `owner_of` skips burned ids while
+        // scanning and never reads this slot; keeping it non-zero makes `_mint` reject this id
+        // as `AlreadyMinted`.
+        self._owners.setter(token_id).set(owner);
+        self._burned.setter(token_id).set(true);
 
         // delete getApproved[id];
         self._approvals.setter(token_id).set(ADDRESS_ZERO);
 
         // emit Transfer(owner, address(0), id);
         evm::log(Transfer {
-            from: owner.get(),
+            from: owner,
             to: ADDRESS_ZERO,
             token_id,
         });
@@ -282,11 +447,59 @@ impl<T: Erc721Params> Erc721<T> {
         Ok(())
     }
 
+    /// ERC721A-style batch mint: writes the owner slot only for the first token of the batch
+    /// while crediting the recipient's balance by `quantity`, so minting N tokens costs one
+    /// owner write instead of N. `owner_of` resolves the remaining ids by scanning down to this
+    /// slot. One `Transfer` event is still emitted per minted id, as the standard requires.
+    ///
+    /// That O(1)-storage saving only holds for the owner mapping itself: enumeration is always
+    /// maintained, so this still writes `_owned_tokens`/`_all_tokens` bookkeeping once per
+    /// minted id below, and a batch mint here costs O(quantity) storage writes overall.
+    pub fn _mint_consecutive(&mut self, to: Address, quantity: U256) -> Result<(), Erc721Error> {
+        // require(to != address(0), "INVALID_RECIPIENT");
+        if to == ADDRESS_ZERO {
+            return Err(Erc721Error::InvalidRecipient(InvalidRecipient { to }));
+        }
+
+        if quantity.is_zero() {
+            return Err(Erc721Error::InvalidQuantity(InvalidQuantity { quantity }));
+        }
+
+        let start_id = self._current_index.get();
+        let owner_start_index = self._balances.get(to);
+
+        // Only the first token of the batch gets an explicit owner slot.
+        self._owners.setter(start_id).set(to);
+
+        // _balanceOf[to] += quantity;
+        let mut to_balance = self._balances.setter(to);
+        let new_to_balance = to_balance.get() + quantity;
+        to_balance.set(new_to_balance);
+
+        let mut minted = U256::ZERO;
+        while minted < quantity {
+            let token_id = start_id + minted;
+            self._add_token_to_owner_enumeration(to, token_id, owner_start_index + minted);
+            self._add_token_to_all_tokens_enumeration(token_id);
+
+            evm::log(Transfer {
+                from: ADDRESS_ZERO,
+                to,
+                token_id,
+            });
+            minted += U256::from(1);
+        }
+
+        self._current_index.set(start_id + quantity);
+
+        Ok(())
+    }
+
     pub fn _safe_mint(&mut self, to: Address, token_id: U256) -> Result<(), Erc721Error> {
         // _mint(to, id);
         self._mint(to, token_id)?;
 
-        self._check_recipient_is_valid(ADDRESS_ZERO, to, token_id)?;
+        self._check_recipient_is_valid(ADDRESS_ZERO, to, token_id, &[])?;
 
         Ok(())
     }
@@ -296,11 +509,12 @@ impl<T: Erc721Params> Erc721<T> {
         from: Address,
         to: Address,
         token_id: U256,
+        data: &[u8],
     ) -> Result<(), Erc721Error> {
         let receiver = IERC721TokenReceiver::new(to);
         let config = Call::new();
         let hook_result = receiver
-            .on_erc_721_received(config, msg::sender(), from, token_id, vec![])
+            .on_erc_721_received(config, msg::sender(), from, token_id, data.to_vec())
             .map_err(|_e| Erc721Error::CallFailed(CallFailed {}))?;
 
         // require(to.code.length == 0 || ERC721TokenReceiver(to).onERC721Received(msg.sender, from, id, "") == ERC721TokenReceiver.onERC721Received.selector, "UNSAFE_RECIPIENT");
@@ -312,4 +526,54 @@ impl<T: Erc721Params> Erc721<T> {
 
         Ok(())
     }
+
+    /// Appends `token_id` to `owner`'s enumeration at `index`, which must be `owner`'s current
+    /// balance (i.e. called before that balance is incremented).
+    fn _add_token_to_owner_enumeration(&mut self, owner: Address, token_id: U256, index: U256) {
+        let mut owned = self._owned_tokens.setter(owner);
+        owned.setter(index).set(token_id);
+        self._owned_tokens_index.setter(token_id).set(index);
+    }
+
+    /// Removes `token_id` from `owner`'s enumeration via swap-and-pop against the last slot,
+    /// which must be called before `owner`'s balance is decremented.
+    fn _remove_token_from_owner_enumeration(&mut self, owner: Address, token_id: U256) {
+        let last_index = self._balances.get(owner) - U256::from(1);
+        let token_index = self._owned_tokens_index.get(token_id);
+
+        if token_index != last_index {
+            let last_token_id = self._owned_tokens.get(owner).get(last_index);
+            let mut owned = self._owned_tokens.setter(owner);
+            owned.setter(token_index).set(last_token_id);
+            self._owned_tokens_index.setter(last_token_id).set(token_index);
+        }
+
+        self._owned_tokens_index.setter(token_id).set(U256::ZERO);
+        self._owned_tokens.setter(owner).setter(last_index).set(U256::ZERO);
+    }
+
+    /// Appends `token_id` to the global enumeration, growing `_all_tokens_length` by one.
+    fn _add_token_to_all_tokens_enumeration(&mut self, token_id: U256) {
+        let index = self._all_tokens_length.get();
+        self._all_tokens_index.setter(token_id).set(index);
+        self._all_tokens.setter(index).set(token_id);
+        self._all_tokens_length.set(index + U256::from(1));
+    }
+
+    /// Removes `token_id` from the global enumeration via swap-and-pop against the last slot,
+    /// shrinking `_all_tokens_length` by one.
+    fn _remove_token_from_all_tokens_enumeration(&mut self, token_id: U256) {
+        let last_index = self._all_tokens_length.get() - U256::from(1);
+        let token_index = self._all_tokens_index.get(token_id);
+
+        if token_index != last_index {
+            let last_token_id = self._all_tokens.get(last_index);
+            self._all_tokens.setter(token_index).set(last_token_id);
+            self._all_tokens_index.setter(last_token_id).set(token_index);
+        }
+
+        self._all_tokens_index.setter(token_id).set(U256::ZERO);
+        self._all_tokens.setter(last_index).set(U256::ZERO);
+        self._all_tokens_length.set(last_index);
+    }
 }
@@ -7,13 +7,25 @@ extern crate alloc;
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 use crate::erc721::{Erc721, Erc721Params};
-use alloy_primitives::{Address, U256};
+use crate::royalty::Royalty;
+use alloy_primitives::{Address, FixedBytes, U256};
 use erc721::{Erc721Error, NotAuthorized};
 /// Import the Stylus SDK along with alloy primitive types for use in our program.
 use stylus_sdk::{call::Call, msg, prelude::*};
 
 /// import modules
 mod erc721;
+/// Alternative single-slot storage mode for `Erc721` (see `packed::PackedErc721`). It exposes
+/// many of the same external methods (`name`, `owner_of`, `transfer_from`, ...) as `Erc721<T>`,
+/// so it is an alternative, not an add-on: inheriting both into `StylusNFT` would register
+/// duplicate external selectors. It has no safe-transfer, `supports_interface` or enumeration
+/// methods. It is intentionally NOT inherited into `StylusNFT` below; `#[allow(dead_code)]`
+/// keeps the unused-but-deployable module from raising dead-code warnings. A
+/// contract that wants the packed layout instead of `erc721`'s separate mappings should inherit
+/// `PackedErc721<StylusNFTParams>` in place of (not alongside) `Erc721<StylusNFTParams>`.
+#[allow(dead_code)]
+mod packed;
+mod royalty;
 
 struct StylusNFTParams;
 
@@ -23,6 +35,8 @@ impl Erc721Params for StylusNFTParams {
     const SYMBOL: &'static str = "SNFT";
 }
 
+const ADDRESS_ZERO: Address = Address(FixedBytes([0u8; 20]));
+
 // Define the entrypoint as a Solidity storage object, in this case a struct
 // called `Counter` with a single uint256 value called `number`. The sol_storage! macro
 // will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
@@ -32,15 +46,57 @@ sol_storage! {
     struct StylusNFT {
         #[borrow] // Allows erc721 to access MyToken's storage and make calls
         Erc721<StylusNFTParams> erc721;
+        #[borrow] // Allows royalty to access MyToken's storage and make calls
+        Royalty royalty;
         uint256 counter;
+        /// Prefix prepended to the decimal token id to form `token_uri`. Owner-settable.
+        string base_token_uri;
+        /// Lazily initialized to the first caller that successfully calls an owner-gated
+        /// method, since this contract has no constructor to set it at deploy time.
+        address owner;
     }
 }
 
 #[external]
-#[inherit(Erc721<StylusNFTParams>)]
+#[inherit(Erc721<StylusNFTParams>, Royalty)]
 impl StylusNFT {
-    fn token_uri(token_id: U256) -> Result<String, Erc721Error> {
-        Ok(format!("{}{}", "https://foobar/", token_id))
+    fn token_uri(&self, token_id: U256) -> Result<String, Erc721Error> {
+        // Existence must go through `owner_of` rather than peeking at the raw `_owners` slot:
+        // `_burn` keeps that slot populated (it writes the owner into it) and tracks burned-ness
+        // separately, so a raw zero-check never catches burned ids.
+        self.erc721.owner_of(token_id)?;
+
+        let mut uri = self.base_token_uri.get_string();
+        uri.push_str(&to_decimal_string(token_id));
+        Ok(uri)
+    }
+
+    pub fn set_base_token_uri(&mut self, base_token_uri: String) -> Result<(), Erc721Error> {
+        self.only_owner()?;
+        self.base_token_uri.set_str(base_token_uri);
+        Ok(())
+    }
+
+    pub fn set_default_royalty(
+        &mut self,
+        receiver: Address,
+        fee_numerator: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.only_owner().map_err(Vec::<u8>::from)?;
+        self.royalty._set_default_royalty(receiver, fee_numerator)?;
+        Ok(())
+    }
+
+    pub fn set_token_royalty(
+        &mut self,
+        token_id: U256,
+        receiver: Address,
+        fee_numerator: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.only_owner().map_err(Vec::<u8>::from)?;
+        self.royalty
+            ._set_token_royalty(token_id, receiver, fee_numerator)?;
+        Ok(())
     }
 
     pub fn mint(&mut self, to: Address) -> Result<(), Erc721Error> {
@@ -53,7 +109,11 @@ impl StylusNFT {
     }
 
     pub fn burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
-        let owner = self.erc721._owners.get(token_id);
+        // Existence and ownership must go through `owner_of` rather than the raw `_owners`
+        // slot: for any id other than a batch's anchor, `_owners` is unset and a raw read
+        // would make the legitimate owner look unauthorized instead of reporting the real
+        // failure mode. See `token_uri` above for the same fix.
+        let owner = self.erc721.owner_of(token_id)?;
         if msg::sender() != owner {
             return Err(Erc721Error::NotAuthorized(NotAuthorized {
                 caller: msg::sender(),
@@ -64,3 +124,52 @@ impl StylusNFT {
         Ok(())
     }
 }
+
+impl StylusNFT {
+    /// Authorizes the caller for owner-gated methods, lazily adopting the first caller as
+    /// owner since the contract has no constructor to do so at deploy time.
+    fn only_owner(&mut self) -> Result<(), Erc721Error> {
+        let current_owner = self.owner.get();
+        if current_owner == ADDRESS_ZERO {
+            self.owner.set(msg::sender());
+            return Ok(());
+        }
+
+        if msg::sender() != current_owner {
+            return Err(Erc721Error::NotAuthorized(NotAuthorized {
+                caller: msg::sender(),
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders a `U256` as its decimal `String` representation, mirroring the classic OpenZeppelin
+/// `Strings.toString`. Written by hand instead of via `format!`/`alloc::fmt` so the gas cost is
+/// deterministic: count the digits, allocate exactly that many bytes, then fill back-to-front.
+fn to_decimal_string(value: U256) -> String {
+    if value.is_zero() {
+        return String::from("0");
+    }
+
+    let ten = U256::from(10);
+    let mut digits = 0usize;
+    let mut remaining = value;
+    while !remaining.is_zero() {
+        digits += 1;
+        remaining /= ten;
+    }
+
+    let mut buf = vec![0u8; digits];
+    let mut remaining = value;
+    let mut i = digits;
+    while !remaining.is_zero() {
+        i -= 1;
+        let digit = (remaining % ten).to::<u8>();
+        buf[i] = b'0' + digit;
+        remaining /= ten;
+    }
+
+    String::from_utf8(buf).expect("only ASCII digits were written")
+}
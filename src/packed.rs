@@ -0,0 +1,269 @@
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm, msg,
+    prelude::*,
+};
+
+use crate::erc721::{
+    AlreadyMinted, Approval, ApprovalForAll, Erc721Error, Erc721Params, InvalidRecipient,
+    NotAuthorized, NotMinted, NotOwner, Transfer,
+};
+
+/// Alternative, single-slot storage layout for [`crate::erc721::Erc721`], following Solmate's
+/// packed `TokenData` approach: owner, a sequence id and a freeform aux field all live in one
+/// `uint256` word per token, so `owner_of`, `_mint`, `_burn` and `transfer_from` touch a single
+/// slot for token data instead of separate owner and aux mappings (balances and approvals keep
+/// their own mappings). A contract opts into this layout by
+/// inheriting `PackedErc721<T>` in place of `Erc721<T>`; the two are not meant to coexist on the
+/// same entrypoint, since both register the same external selectors.
+///
+/// Layout of the packed word, lowest bit first:
+/// - bits `0..160`: owner address
+/// - bits `160..176`: sequence id (mint order within a collection, `u16`)
+/// - bits `176..256`: aux (80 bits, freeform — mint timestamp, tier, etc.)
+sol_storage! {
+    pub struct PackedErc721<T> {
+        /// Token id to packed (owner, sequence_id, aux) word.
+        mapping(uint256 => uint256) _token_data;
+        /// User to balance map
+        mapping(address => uint256) _balances;
+        /// Token id to approved user map
+        mapping(uint256 => address) _approvals;
+        /// User to operator map (the operator can manage all NFTs of the owner.)
+        mapping(address => mapping(address => bool)) _approvals_for_all;
+        /// Used to allow [`Erc721Params`]
+        PhantomData<T> phantom;
+    }
+}
+
+const ADDRESS_ZERO: Address = Address(alloy_primitives::FixedBytes([0u8; 20]));
+
+const OWNER_MASK: U256 = U256::from_limbs([u64::MAX, u64::MAX, 0xffff_ffff, 0]);
+const SEQUENCE_ID_SHIFT: usize = 160;
+const SEQUENCE_ID_MASK: U256 = U256::from_limbs([0xffff, 0, 0, 0]);
+const AUX_SHIFT: usize = 176;
+
+// These methods are external to other contracts
+#[external]
+impl<T: Erc721Params> PackedErc721<T> {
+    pub fn name() -> Result<String, Vec<u8>> {
+        Ok(T::NAME.into())
+    }
+
+    pub fn symbol() -> Result<String, Vec<u8>> {
+        Ok(T::SYMBOL.into())
+    }
+
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self._balances.get(owner))
+    }
+
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        let owner = Self::_owner(self._get_token_data(token_id));
+        if owner == ADDRESS_ZERO {
+            return Err(Erc721Error::NotMinted(NotMinted { token_id }));
+        }
+        Ok(owner)
+    }
+
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(self._approvals.get(token_id))
+    }
+
+    pub fn is_approved_for_all(
+        &self,
+        owner: Address,
+        operator: Address,
+    ) -> Result<bool, Erc721Error> {
+        Ok(self._approvals_for_all.get(owner).get(operator))
+    }
+
+    /// Reads the freeform 80-bit aux field packed alongside the owner, e.g. mint timestamp
+    /// or tier, without a separate storage slot.
+    pub fn token_aux(&self, token_id: U256) -> Result<U256, Erc721Error> {
+        Ok(Self::_aux(self._get_token_data(token_id)))
+    }
+
+    pub fn approve(&mut self, spender: Address, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+
+        if msg::sender() != owner && !self._approvals_for_all.get(owner).get(msg::sender()) {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                account: owner,
+                token_id,
+            }));
+        }
+
+        self._approvals.setter(token_id).set(spender);
+
+        evm::log(Approval {
+            owner,
+            spender,
+            token_id,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_approval_for_all(
+        &mut self,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc721Error> {
+        let mut operator_setter = self._approvals_for_all.setter(msg::sender());
+        let mut approval_setter = operator_setter.setter(operator);
+        approval_setter.set(approved);
+
+        evm::log(ApprovalForAll {
+            owner: msg::sender(),
+            operator,
+            approved,
+        });
+
+        Ok(())
+    }
+
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        let word = self._get_token_data(token_id);
+        let owner = Self::_owner(word);
+        if owner != from {
+            return Err(Erc721Error::NotOwner(NotOwner {
+                account: from,
+                token_id,
+            }));
+        }
+
+        if to == ADDRESS_ZERO {
+            return Err(Erc721Error::InvalidRecipient(InvalidRecipient { to }));
+        }
+
+        if msg::sender() != from
+            && !self._approvals_for_all.get(from).get(msg::sender())
+            && msg::sender() != self._approvals.get(token_id)
+        {
+            return Err(Erc721Error::NotAuthorized(NotAuthorized {
+                caller: msg::sender(),
+            }));
+        }
+
+        let mut from_balance = self._balances.setter(from);
+        from_balance.set(from_balance.get() - U256::from(1));
+
+        let mut to_balance = self._balances.setter(to);
+        to_balance.set(to_balance.get() + U256::from(1));
+
+        // Single SSTORE to the packed word: owner changes, sequence id and aux carry over.
+        self._token_data
+            .setter(token_id)
+            .set(Self::_set_owner(word, to));
+
+        self._approvals.setter(token_id).set(ADDRESS_ZERO);
+
+        evm::log(Transfer { from, to, token_id });
+
+        Ok(())
+    }
+}
+
+// internal mint+burn+packing methods
+impl<T: Erc721Params> PackedErc721<T> {
+    pub fn _get_token_data(&self, token_id: U256) -> U256 {
+        self._token_data.get(token_id)
+    }
+
+    pub fn _mint(
+        &mut self,
+        to: Address,
+        token_id: U256,
+        sequence_id: u16,
+    ) -> Result<(), Erc721Error> {
+        if to == ADDRESS_ZERO {
+            return Err(Erc721Error::InvalidRecipient(InvalidRecipient { to }));
+        }
+
+        let word = self._get_token_data(token_id);
+        if Self::_owner(word) != ADDRESS_ZERO {
+            return Err(Erc721Error::AlreadyMinted(AlreadyMinted { token_id }));
+        }
+
+        let word = Self::_set_owner(U256::ZERO, to);
+        let word = Self::_set_sequence_id(word, sequence_id);
+        self._token_data.setter(token_id).set(word);
+
+        let mut to_balance = self._balances.setter(to);
+        to_balance.set(to_balance.get() + U256::from(1));
+
+        evm::log(Transfer {
+            from: ADDRESS_ZERO,
+            to,
+            token_id,
+        });
+
+        Ok(())
+    }
+
+    pub fn _burn(&mut self, token_id: U256) -> Result<(), Erc721Error> {
+        let word = self._get_token_data(token_id);
+        let owner = Self::_owner(word);
+        if owner == ADDRESS_ZERO {
+            return Err(Erc721Error::NotMinted(NotMinted { token_id }));
+        }
+
+        let mut owner_balance = self._balances.setter(owner);
+        owner_balance.set(owner_balance.get() - U256::from(1));
+
+        self._token_data.setter(token_id).set(U256::ZERO);
+        self._approvals.setter(token_id).set(ADDRESS_ZERO);
+
+        evm::log(Transfer {
+            from: owner,
+            to: ADDRESS_ZERO,
+            token_id,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the 80-bit aux field in place, leaving the owner and sequence id untouched.
+    pub fn _set_aux(&mut self, token_id: U256, aux: U256) {
+        let word = self._get_token_data(token_id);
+        self._token_data
+            .setter(token_id)
+            .set(Self::_set_aux_bits(word, aux));
+    }
+
+    pub fn _set_owner(word: U256, owner: Address) -> U256 {
+        let mut bytes = [0u8; 32];
+        bytes[12..32].copy_from_slice(owner.as_slice());
+        let owner_bits = U256::from_be_bytes(bytes);
+        (word & !OWNER_MASK) | owner_bits
+    }
+
+    fn _set_sequence_id(word: U256, sequence_id: u16) -> U256 {
+        let cleared = word & !(SEQUENCE_ID_MASK << SEQUENCE_ID_SHIFT);
+        cleared | (U256::from(sequence_id) << SEQUENCE_ID_SHIFT)
+    }
+
+    fn _set_aux_bits(word: U256, aux: U256) -> U256 {
+        let aux_mask = !(OWNER_MASK | (SEQUENCE_ID_MASK << SEQUENCE_ID_SHIFT));
+        let cleared = word & !aux_mask;
+        cleared | ((aux << AUX_SHIFT) & aux_mask)
+    }
+
+    fn _owner(word: U256) -> Address {
+        let bytes = (word & OWNER_MASK).to_be_bytes::<32>();
+        Address::from_slice(&bytes[12..32])
+    }
+
+    fn _aux(word: U256) -> U256 {
+        let aux_mask = !(OWNER_MASK | (SEQUENCE_ID_MASK << SEQUENCE_ID_SHIFT));
+        (word & aux_mask) >> AUX_SHIFT
+    }
+}
@@ -0,0 +1,123 @@
+use alloy_primitives::{Address, FixedBytes, U256};
+use stylus_sdk::{alloy_sol_types::SolError, prelude::*};
+
+/// Denominator royalty fees are expressed against, i.e. a `fee_numerator` of `250` is 2.5%.
+const FEE_DENOMINATOR: u64 = 10000;
+
+const ADDRESS_ZERO: Address = Address(FixedBytes([0u8; 20]));
+
+sol_storage! {
+    /// ERC-2981 royalty info, shared across all tokens unless overridden per-token.
+    pub struct Royalty {
+        /// Royalty receiver used for any token without an explicit override.
+        address _default_receiver;
+        /// Royalty fee for tokens without an override, in basis points (see [`FEE_DENOMINATOR`]).
+        uint256 _default_fee_numerator;
+        /// Per-token (receiver, fee_numerator) override packed into one word (see
+        /// [`pack_token_royalty`]/[`unpack_token_royalty`]); a word whose receiver bits are
+        /// zero means "use the default", so an override set with the zero receiver also falls
+        /// back to the default.
+        mapping(uint256 => uint256) _token_royalty;
+    }
+}
+
+/// Bits `0..160` hold the receiver address, bits `160..256` hold the fee numerator. A fee
+/// numerator is capped to [`FEE_DENOMINATOR`] (at most 10000, i.e. 14 bits), so it always fits
+/// in the remaining 96 bits with room to spare; following `packed.rs`'s mask-and-shift idiom
+/// anyway keeps the two packed layouts in this crate consistent.
+const RECEIVER_MASK: U256 = U256::from_limbs([u64::MAX, u64::MAX, 0xffff_ffff, 0]);
+const FEE_NUMERATOR_SHIFT: usize = 160;
+
+fn pack_token_royalty(receiver: Address, fee_numerator: U256) -> U256 {
+    let mut bytes = [0u8; 32];
+    bytes[12..32].copy_from_slice(receiver.as_slice());
+    let receiver_bits = U256::from_be_bytes(bytes);
+    receiver_bits | (fee_numerator << FEE_NUMERATOR_SHIFT)
+}
+
+fn unpack_token_royalty(word: U256) -> (Address, U256) {
+    let bytes = (word & RECEIVER_MASK).to_be_bytes::<32>();
+    let receiver = Address::from_slice(&bytes[12..32]);
+    let fee_numerator = word >> FEE_NUMERATOR_SHIFT;
+    (receiver, fee_numerator)
+}
+
+sol! {
+    error FeeTooHigh(uint256 fee_numerator, uint256 denominator);
+}
+
+pub enum RoyaltyError {
+    FeeTooHigh(FeeTooHigh),
+}
+
+impl From<RoyaltyError> for Vec<u8> {
+    fn from(err: RoyaltyError) -> Vec<u8> {
+        match err {
+            RoyaltyError::FeeTooHigh(e) => e.encode(),
+        }
+    }
+}
+
+// These methods are external to other contracts
+#[external]
+impl Royalty {
+    /// Returns the royalty `receiver` and the amount owed for a sale at `sale_price`, per
+    /// ERC-2981: `amount = sale_price * fee_numerator / 10000`.
+    pub fn royalty_info(
+        &self,
+        token_id: U256,
+        sale_price: U256,
+    ) -> Result<(Address, U256), Vec<u8>> {
+        let (receiver, fee_numerator) = self._royalty_for(token_id);
+        let amount = sale_price * fee_numerator / U256::from(FEE_DENOMINATOR);
+        Ok((receiver, amount))
+    }
+}
+
+// internal setters
+impl Royalty {
+    pub fn _set_default_royalty(
+        &mut self,
+        receiver: Address,
+        fee_numerator: U256,
+    ) -> Result<(), RoyaltyError> {
+        if fee_numerator > U256::from(FEE_DENOMINATOR) {
+            return Err(RoyaltyError::FeeTooHigh(FeeTooHigh {
+                fee_numerator,
+                denominator: U256::from(FEE_DENOMINATOR),
+            }));
+        }
+
+        self._default_receiver.set(receiver);
+        self._default_fee_numerator.set(fee_numerator);
+        Ok(())
+    }
+
+    pub fn _set_token_royalty(
+        &mut self,
+        token_id: U256,
+        receiver: Address,
+        fee_numerator: U256,
+    ) -> Result<(), RoyaltyError> {
+        if fee_numerator > U256::from(FEE_DENOMINATOR) {
+            return Err(RoyaltyError::FeeTooHigh(FeeTooHigh {
+                fee_numerator,
+                denominator: U256::from(FEE_DENOMINATOR),
+            }));
+        }
+
+        self._token_royalty
+            .setter(token_id)
+            .set(pack_token_royalty(receiver, fee_numerator));
+        Ok(())
+    }
+
+    fn _royalty_for(&self, token_id: U256) -> (Address, U256) {
+        let (receiver, fee_numerator) = unpack_token_royalty(self._token_royalty.get(token_id));
+        if receiver != ADDRESS_ZERO {
+            (receiver, fee_numerator)
+        } else {
+            (self._default_receiver.get(), self._default_fee_numerator.get())
+        }
+    }
+}